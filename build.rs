@@ -2,6 +2,7 @@ extern crate version_check;
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo::rustc-check-cfg=cfg(nightly)");
     match version_check::Channel::read() {
         Some(c) if c.is_nightly() => println!("cargo:rustc-cfg=nightly"),
         _ => (),
@@ -0,0 +1,39 @@
+use io::ErrorType;
+use Result;
+
+/// A non-blocking [`Read`](super::Read) with an internal buffer that can be
+/// inspected directly.
+///
+/// This is the non-blocking analog of `std::io::BufRead`. It lets a decoder
+/// peek at whatever bytes are already buffered, search them for a delimiter,
+/// and [`consume`](BufRead::consume) exactly the framed region, without
+/// copying through an intermediate scratch buffer.
+pub trait BufRead: ErrorType {
+    /// Returns the contents of the internal buffer, filling it with more
+    /// data from the inner source if it is empty.
+    ///
+    /// If no data is currently available but the source may still produce
+    /// more later, this returns `Err(nb::Error::WouldBlock)`; the caller
+    /// should try again once the source is expected to have made progress.
+    /// Once the source is permanently exhausted, this instead returns
+    /// `Ok(&[])`, mirroring how [`Read::read`](super::Read::read) returns
+    /// `Ok(0)` rather than blocking forever on end-of-stream. The returned
+    /// slice stays valid, and its contents unchanged, until
+    /// [`consume`](BufRead::consume) is called.
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error>;
+
+    /// Marks `amt` bytes of the buffer returned by
+    /// [`fill_buf`](BufRead::fill_buf) as read, so that they are not
+    /// returned again by a future call to `fill_buf`.
+    fn consume(&mut self, amt: usize);
+}
+
+impl BufRead for &[u8] {
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(*self)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt..];
+    }
+}
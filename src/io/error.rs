@@ -0,0 +1,96 @@
+use core::convert::Infallible;
+use core::fmt;
+
+/// Error kinds shared by [`Read`](super::Read) and [`Write`](super::Write)
+/// implementations.
+///
+/// This loosely mirrors `embedded-io`'s `ErrorKind` so that generic code can
+/// match on broad categories of failure (e.g. "the peer hung up") without
+/// knowing the concrete error type of the underlying peripheral.
+///
+/// This list is not exhaustive: new variants may be added at any time,
+/// including in patch releases.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Unspecified error kind.
+    Other,
+    /// An entity was not connected.
+    NotConnected,
+    /// The connection was reset by the other side.
+    ConnectionReset,
+    /// The operation was interrupted and should be retried if appropriate.
+    Interrupted,
+    /// Invalid data was encountered while reading or writing.
+    InvalidData,
+    /// Out of memory or out of buffer space.
+    OutOfMemory,
+    /// A write returned `Ok(0)`, meaning no bytes could be written even
+    /// though some were requested.
+    WriteZero,
+}
+
+impl ErrorKind {
+    /// Returns a short, human-readable description of this error kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Other => "other I/O error",
+            ErrorKind::NotConnected => "entity not connected",
+            ErrorKind::ConnectionReset => "connection reset",
+            ErrorKind::Interrupted => "operation interrupted",
+            ErrorKind::InvalidData => "invalid data",
+            ErrorKind::OutOfMemory => "out of memory",
+            ErrorKind::WriteZero => "write returned Ok(0)",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error that can be reported by a [`Read`](super::Read) or
+/// [`Write`](super::Write) implementation.
+///
+/// Implement this for a HAL's concrete error type so that generic drivers
+/// can recover an [`ErrorKind`] without depending on the concrete type.
+pub trait Error: fmt::Debug {
+    /// Returns the categorization of this error.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+// `!` itself only implements traits on nightly (`#![feature(never_type)]`), so
+// this blanket is nightly-only. Every impl in this crate that needs an
+// infallible error type uses `core::convert::Infallible` instead, which is
+// stable; this just extends the same courtesy to callers who already use `!`
+// as their own HAL's error type.
+#[cfg(nightly)]
+impl Error for ! {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// Associates an error type with an I/O object, independently of whichever
+/// operation (`Read`, `Write`, ...) ends up producing it.
+///
+/// `Read` and `Write` both require this trait instead of declaring their own
+/// `Error` associated type, so that a single concrete type only has to name
+/// its error type once, and generic code can refer to `T::Error` without
+/// picking a particular trait to import it from.
+pub trait ErrorType {
+    /// Error type of all the I/O operations on this type.
+    type Error: Error;
+}
+
+impl<T: ?Sized + ErrorType> ErrorType for &mut T {
+    type Error = T::Error;
+}
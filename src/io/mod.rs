@@ -0,0 +1,18 @@
+//! Non-blocking analogs of `std::io`'s `Read` and `Write` traits.
+//!
+//! This module is gated behind the `unstable` feature: the traits and their
+//! error model are still settling and may change in a patch release.
+
+mod buf_read;
+mod error;
+mod pipe;
+mod rate_limited;
+mod read;
+mod write;
+
+pub use self::buf_read::BufRead;
+pub use self::error::{Error, ErrorKind, ErrorType};
+pub use self::pipe::{Consumer, Pipe, Producer};
+pub use self::rate_limited::{Clock, RateLimited};
+pub use self::read::{Read, ReadExact, ReadExactError};
+pub use self::write::{Write, WriteAll, WriteAllError};
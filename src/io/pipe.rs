@@ -0,0 +1,214 @@
+use core::cell::Cell;
+use core::cmp;
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::ptr;
+
+use io::{ErrorType, Read, Write};
+use Result;
+
+/// A fixed-capacity, alloc-free ring buffer that can be [`split`](Pipe::split)
+/// into a non-blocking [`Producer`]/[`Consumer`] pair.
+///
+/// This gives driver authors a deterministic loopback for unit-testing code
+/// that consumes the `nb` [`Read`]/[`Write`] traits and the [`block!`] /
+/// [`fut!`] macros without real hardware, and doubles as a lightweight
+/// channel between cooperatively-scheduled tasks.
+///
+/// [`block!`]: crate::block
+/// [`fut!`]: crate::fut
+pub struct Pipe<'b> {
+    buf: *mut u8,
+    capacity: usize,
+    read: Cell<usize>,
+    count: Cell<usize>,
+    closed: Cell<bool>,
+    _marker: PhantomData<&'b mut [u8]>,
+}
+
+impl<'b> Pipe<'b> {
+    /// Creates a pipe backed by `buf`. The pipe can hold at most `buf.len()`
+    /// bytes at a time.
+    pub fn new(buf: &'b mut [u8]) -> Self {
+        Pipe {
+            capacity: buf.len(),
+            buf: buf.as_mut_ptr(),
+            read: Cell::new(0),
+            count: Cell::new(0),
+            closed: Cell::new(false),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Splits the pipe into its writable and readable halves.
+    pub fn split(&mut self) -> (Producer<'_, 'b>, Consumer<'_, 'b>) {
+        (Producer { pipe: self }, Consumer { pipe: self })
+    }
+
+    // Safety: `idx` is always taken modulo `capacity`, and `buf` points to
+    // `capacity` initialized bytes for the lifetime of the `Pipe`.
+    unsafe fn slot(&self, idx: usize) -> *mut u8 {
+        self.buf.add(idx % self.capacity)
+    }
+}
+
+/// The writable half of a [`Pipe`], created by [`Pipe::split`].
+pub struct Producer<'p, 'b: 'p> {
+    pipe: &'p Pipe<'b>,
+}
+
+/// The readable half of a [`Pipe`], created by [`Pipe::split`].
+pub struct Consumer<'p, 'b: 'p> {
+    pipe: &'p Pipe<'b>,
+}
+
+impl<'p, 'b> ErrorType for Producer<'p, 'b> {
+    type Error = Infallible;
+}
+
+impl<'p, 'b> Drop for Producer<'p, 'b> {
+    /// Dropping the producer closes the pipe, just like an explicit
+    /// [`close`](Write::close) call, so a [`Consumer`] blocked on
+    /// `WouldBlock` sees end-of-stream instead of spinning forever once its
+    /// `Producer` goes away.
+    fn drop(&mut self) {
+        self.pipe.closed.set(true);
+    }
+}
+
+impl<'p, 'b> Write for Producer<'p, 'b> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let free = self.pipe.capacity - self.pipe.count.get();
+        if free == 0 {
+            return Err(::Error::WouldBlock);
+        }
+
+        let n = cmp::min(free, buf.len());
+        let write_pos = self.pipe.read.get() + self.pipe.count.get();
+        for (i, &byte) in buf[..n].iter().enumerate() {
+            unsafe { ptr::write(self.pipe.slot(write_pos + i), byte) };
+        }
+        self.pipe.count.set(self.pipe.count.get() + n);
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Marks the pipe as closed, causing the [`Consumer`] to see end-of-stream
+    /// (`Ok(0)`) once the buffered bytes have been drained.
+    fn close(&mut self) -> Result<(), Self::Error> {
+        self.pipe.closed.set(true);
+        Ok(())
+    }
+}
+
+impl<'p, 'b> ErrorType for Consumer<'p, 'b> {
+    type Error = Infallible;
+}
+
+impl<'p, 'b> Read for Consumer<'p, 'b> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let count = self.pipe.count.get();
+        if count == 0 {
+            return if self.pipe.closed.get() {
+                Ok(0)
+            } else {
+                Err(::Error::WouldBlock)
+            };
+        }
+
+        let n = cmp::min(count, buf.len());
+        let read_pos = self.pipe.read.get();
+        for (i, slot) in buf[..n].iter_mut().enumerate() {
+            *slot = unsafe { ptr::read(self.pipe.slot(read_pos + i)) };
+        }
+        self.pipe.read.set((read_pos + n) % self.pipe.capacity);
+        self.pipe.count.set(count - n);
+
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_writes_wrap_around_the_backing_buffer() {
+        let mut backing = [0u8; 4];
+        let mut pipe = Pipe::new(&mut backing);
+        let (mut tx, mut rx) = pipe.split();
+
+        assert_eq!(tx.write(&[1, 2, 3]), Ok(3));
+
+        let mut out = [0u8; 2];
+        assert_eq!(rx.read(&mut out), Ok(2));
+        assert_eq!(out, [1, 2]);
+
+        // Only 3 bytes are free (1 byte of "3" is still buffered), so this
+        // write has to wrap the write cursor back around to the front.
+        assert_eq!(tx.write(&[4, 5, 6]), Ok(3));
+
+        let mut out = [0u8; 4];
+        assert_eq!(rx.read(&mut out), Ok(4));
+        assert_eq!(out, [3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn write_would_block_once_the_buffer_is_full() {
+        let mut backing = [0u8; 2];
+        let mut pipe = Pipe::new(&mut backing);
+        let (mut tx, _rx) = pipe.split();
+
+        assert_eq!(tx.write(&[1, 2]), Ok(2));
+        assert_eq!(tx.write(&[3]), Err(::Error::WouldBlock));
+    }
+
+    #[test]
+    fn read_would_block_on_an_empty_pipe() {
+        let mut backing = [0u8; 2];
+        let mut pipe = Pipe::new(&mut backing);
+        let (_tx, mut rx) = pipe.split();
+
+        let mut out = [0u8; 1];
+        assert_eq!(rx.read(&mut out), Err(::Error::WouldBlock));
+    }
+
+    #[test]
+    fn closing_the_producer_surfaces_eof_once_drained() {
+        let mut backing = [0u8; 2];
+        let mut pipe = Pipe::new(&mut backing);
+        let (mut tx, mut rx) = pipe.split();
+
+        assert_eq!(tx.write(&[9]), Ok(1));
+        assert_eq!(tx.close(), Ok(()));
+
+        let mut out = [0u8; 1];
+        assert_eq!(rx.read(&mut out), Ok(1));
+        assert_eq!(out, [9]);
+        // The buffered byte has now been drained, so the closed producer
+        // should surface end-of-stream rather than `WouldBlock`.
+        assert_eq!(rx.read(&mut out), Ok(0));
+    }
+
+    #[test]
+    fn dropping_the_producer_surfaces_eof_once_drained() {
+        let mut backing = [0u8; 2];
+        let mut pipe = Pipe::new(&mut backing);
+        let (mut tx, mut rx) = pipe.split();
+
+        assert_eq!(tx.write(&[9]), Ok(1));
+        drop(tx);
+
+        let mut out = [0u8; 1];
+        assert_eq!(rx.read(&mut out), Ok(1));
+        assert_eq!(out, [9]);
+        // Dropping the producer without an explicit `close()` must still
+        // signal end-of-stream, or a `Consumer` in `block!`/`fut!` would
+        // spin on `WouldBlock` forever.
+        assert_eq!(rx.read(&mut out), Ok(0));
+    }
+}
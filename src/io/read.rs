@@ -1,14 +1,11 @@
 use core::cmp;
+use core::convert::Infallible;
 
+use io::{Error as IoError, ErrorKind, ErrorType};
 use Result;
 
 /// Non-blocking reader trait
-pub trait Read {
-    /// An enumeration of possible errors
-    ///
-    /// May be `!` (`never_type`) for infallible implementations
-    type Error;
-
+pub trait Read: ErrorType {
     /// Pull some bytes from this source into the specified buffer, returning how many bytes were
     /// read.
     ///
@@ -22,17 +19,17 @@ pub trait Read {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
 }
 
-impl<'a, R: ?Sized + Read> Read for &'a mut R {
-    type Error = R::Error;
-
+impl<R: ?Sized + Read> Read for &mut R {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
         (**self).read(buf)
     }
 }
 
-impl<'a> Read for &'a [u8] {
-    type Error = !;
+impl ErrorType for &[u8] {
+    type Error = Infallible;
+}
 
+impl Read for &[u8] {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
         let len = cmp::min(self.len(), buf.len());
         let (head, tail) = self.split_at(len);
@@ -41,3 +38,91 @@ impl<'a> Read for &'a [u8] {
         Ok(len)
     }
 }
+
+/// Error produced by [`ReadExact`] in addition to whatever the underlying
+/// [`Read`] implementation may report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReadExactError<E> {
+    /// The underlying reader reported an error.
+    Other(E),
+    /// The reader returned `Ok(0)` before `buf` was completely filled.
+    UnexpectedEof,
+}
+
+impl<E: IoError> IoError for ReadExactError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ReadExactError::Other(e) => e.kind(),
+            ReadExactError::UnexpectedEof => ErrorKind::Other,
+        }
+    }
+}
+
+/// A re-pollable `Read::read` loop that fills a buffer completely.
+///
+/// `Read::read` only moves as many bytes as are immediately available, and
+/// `&mut self` has nowhere to stash how much of `buf` has been filled
+/// already, so the cursor lives here instead. Call [`poll`](ReadExact::poll)
+/// with the same reader until it stops returning `Err(WouldBlock)`; this
+/// mirrors `std::io::Read::read_exact`, but in non-blocking, state-preserving
+/// form.
+pub struct ReadExact<'b> {
+    buf: &'b mut [u8],
+    filled: usize,
+}
+
+impl<'b> ReadExact<'b> {
+    /// Creates a new `ReadExact` that will fill `buf` completely.
+    pub fn new(buf: &'b mut [u8]) -> Self {
+        ReadExact { buf, filled: 0 }
+    }
+
+    /// Drives `reader` until `buf` is filled.
+    ///
+    /// Returns `Err(WouldBlock)` while bytes are still outstanding, and
+    /// `Ok(())` once `buf` has been completely written to.
+    pub fn poll<R>(&mut self, reader: &mut R) -> Result<(), ReadExactError<R::Error>>
+    where
+        R: ?Sized + Read,
+    {
+        while self.filled < self.buf.len() {
+            match reader.read(&mut self.buf[self.filled..]) {
+                Ok(0) => return Err(::Error::Other(ReadExactError::UnexpectedEof)),
+                Ok(n) => self.filled += n,
+                Err(::Error::WouldBlock) => return Err(::Error::WouldBlock),
+                Err(::Error::Other(e)) => return Err(::Error::Other(ReadExactError::Other(e))),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_the_buffer_from_a_slice_reader() {
+        let data = [1u8, 2, 3, 4];
+        let mut reader: &[u8] = &data;
+
+        let mut out = [0u8; 4];
+        let mut op = ReadExact::new(&mut out);
+        assert_eq!(op.poll(&mut reader), Ok(()));
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn reports_unexpected_eof_when_the_source_runs_dry() {
+        let data = [1u8, 2, 3];
+        let mut reader: &[u8] = &data;
+
+        let mut out = [0u8; 5];
+        let mut op = ReadExact::new(&mut out);
+        assert_eq!(
+            op.poll(&mut reader),
+            Err(::Error::Other(ReadExactError::UnexpectedEof))
+        );
+    }
+}
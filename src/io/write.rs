@@ -1,14 +1,11 @@
+use core::convert::Infallible;
 use core::{cmp, mem};
 
+use io::{Error as IoError, ErrorKind, ErrorType};
 use Result;
 
 /// Non-blocking writer trait
-pub trait Write {
-    /// An enumeration of possible errors
-    ///
-    /// May be `!` (`never_type`) for infallible implementations
-    type Error;
-
+pub trait Write: ErrorType {
     /// Push some bytes into this source from the specified buffer, returning how many bytes were
     /// written.
     ///
@@ -36,9 +33,7 @@ pub trait Write {
     fn close(&mut self) -> Result<(), Self::Error>;
 }
 
-impl<'a, W: ?Sized + Write> Write for &'a mut W {
-    type Error = W::Error;
-
+impl<W: ?Sized + Write> Write for &mut W {
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
         (**self).write(buf)
     }
@@ -52,12 +47,14 @@ impl<'a, W: ?Sized + Write> Write for &'a mut W {
     }
 }
 
-impl<'a> Write for &'a mut [u8] {
-    type Error = !;
+impl ErrorType for &mut [u8] {
+    type Error = Infallible;
+}
 
+impl Write for &mut [u8] {
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
         let len = cmp::min(self.len(), buf.len());
-        let (head, tail) = mem::replace(self, &mut []).split_at_mut(len);
+        let (head, tail) = mem::take(self).split_at_mut(len);
         head.copy_from_slice(&buf[..len]);
         *self = tail;
         Ok(len)
@@ -71,3 +68,62 @@ impl<'a> Write for &'a mut [u8] {
         Ok(())
     }
 }
+
+/// Error produced by [`WriteAll`] in addition to whatever the underlying
+/// [`Write`] implementation may report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WriteAllError<E> {
+    /// The underlying writer reported an error.
+    Other(E),
+    /// The writer returned `Ok(0)` before `buf` was completely written.
+    WriteZero,
+}
+
+impl<E: IoError> IoError for WriteAllError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            WriteAllError::Other(e) => e.kind(),
+            WriteAllError::WriteZero => ErrorKind::WriteZero,
+        }
+    }
+}
+
+/// A re-pollable `Write::write` loop that writes a buffer completely.
+///
+/// `Write::write` only moves as many bytes as the destination can currently
+/// accept, and `&mut self` has nowhere to stash how much of `buf` has been
+/// written already, so the cursor lives here instead. Call
+/// [`poll`](WriteAll::poll) with the same writer until it stops returning
+/// `Err(WouldBlock)`; this mirrors `std::io::Write::write_all`, but in
+/// non-blocking, state-preserving form.
+pub struct WriteAll<'b> {
+    buf: &'b [u8],
+    sent: usize,
+}
+
+impl<'b> WriteAll<'b> {
+    /// Creates a new `WriteAll` that will write `buf` completely.
+    pub fn new(buf: &'b [u8]) -> Self {
+        WriteAll { buf, sent: 0 }
+    }
+
+    /// Drives `writer` until `buf` has been completely written.
+    ///
+    /// Returns `Err(WouldBlock)` while bytes are still outstanding, and
+    /// `Ok(())` once all of `buf` has been written.
+    pub fn poll<W>(&mut self, writer: &mut W) -> Result<(), WriteAllError<W::Error>>
+    where
+        W: ?Sized + Write,
+    {
+        while self.sent < self.buf.len() {
+            match writer.write(&self.buf[self.sent..]) {
+                Ok(0) => return Err(::Error::Other(WriteAllError::WriteZero)),
+                Ok(n) => self.sent += n,
+                Err(::Error::WouldBlock) => return Err(::Error::WouldBlock),
+                Err(::Error::Other(e)) => return Err(::Error::Other(WriteAllError::Other(e))),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -198,13 +198,19 @@
 //! # Features
 //!
 //! - `defmt-0-3` - unstable feature which adds [`defmt::Format`] impl for [`Error`].
+//! - `unstable` - unstable feature which adds the [`io`] module with non-blocking
+//!   `Read`/`Write` traits.
 
 #![no_std]
+#![cfg_attr(all(nightly, feature = "unstable"), feature(never_type))]
 
 use core::fmt;
 use core::future::Future;
 use core::pin::Pin;
-use core::task::{Context, Poll};
+use core::task::{Context, Poll, Waker};
+
+#[cfg(feature = "unstable")]
+pub mod io;
 
 /// A non-blocking result
 pub type Result<T, E> = ::core::result::Result<T, Error<E>>;
@@ -297,26 +303,125 @@ macro_rules! block {
     };
 }
 
-pub struct NbFuture<Ok, Err, Gen: FnMut() -> Result<Ok, Err>> {
+/// The error returned by [`block_timeout!`]: either the error reported by
+/// `$op`, or a timeout because `$timer` elapsed (or errored) before `$op`
+/// stopped returning `WouldBlock`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeoutError<E> {
+    /// The operation itself reported an error.
+    Other(E),
+    /// The deadline was reached before the operation completed.
+    TimedOut,
+}
+
+impl<E> fmt::Debug for TimeoutError<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TimeoutError::Other(ref e) => fmt::Debug::fmt(e, f),
+            TimeoutError::TimedOut => f.write_str("TimedOut"),
+        }
+    }
+}
+
+/// Like [`block!`], but gives up once `$timer` elapses instead of spinning
+/// forever.
+///
+/// `$op` is polled every iteration, and `$timer` is only polled while `$op`
+/// is still returning `WouldBlock`, so a countdown timer started before the
+/// loop measures the deadline for the whole operation rather than
+/// per-iteration. This is the "read a byte but give up after N ms" pattern
+/// that every serial driver reimplements.
+///
+/// # Input
+///
+/// - `$op`, an expression that evaluates to `nb::Result<T, E>`
+/// - `$timer`, an expression that evaluates to `nb::Result<(), TE>` (for
+///   example a HAL countdown timer's `wait()`), where `Ok(())` means the
+///   deadline has been reached
+///
+/// # Output
+///
+/// - `Ok(t)` if `$op` evaluates to `Ok(t)`
+/// - `Err(TimeoutError::Other(e))` if `$op` evaluates to
+///   `Err(nb::Error::Other(e))`
+/// - `Err(TimeoutError::TimedOut)` if `$timer` elapses (or itself errors)
+///   before `$op` completes
+#[macro_export]
+macro_rules! block_timeout {
+    ($op:expr, $timer:expr) => {
+        loop {
+            #[allow(unreachable_patterns)]
+            match $op {
+                Err($crate::Error::Other(e)) =>
+                {
+                    #[allow(unreachable_code)]
+                    break Err($crate::TimeoutError::Other(e))
+                }
+                Err($crate::Error::WouldBlock) => {
+                    #[allow(unreachable_patterns)]
+                    match $timer {
+                        Err($crate::Error::WouldBlock) => {}
+                        _ => break Err($crate::TimeoutError::TimedOut),
+                    }
+                }
+                Ok(x) => break Ok(x),
+            }
+        }
+    };
+}
+
+/// The default waker hook used when `NbFuture` is built via [`From`] rather
+/// than [`NbFuture::with_waker_hook`]: it just reschedules the task, so the
+/// future keeps being polled even on an executor that parks tasks between
+/// wakeups.
+fn reschedule_waker_hook(waker: &Waker) {
+    waker.wake_by_ref();
+}
+
+pub struct NbFuture<Ok, Err, Gen: FnMut() -> Result<Ok, Err>, Hook: FnMut(&Waker) = fn(&Waker)> {
     gen: Gen,
+    hook: Hook,
 }
 
 impl<Ok, Err, Gen: FnMut() -> Result<Ok, Err>> From<Gen> for NbFuture<Ok, Err, Gen> {
     fn from(gen: Gen) -> Self {
-        Self { gen }
+        Self {
+            gen,
+            hook: reschedule_waker_hook,
+        }
     }
 }
 
-impl<Ok, Err, Gen: FnMut() -> Result<Ok, Err>> Future for NbFuture<Ok, Err, Gen> {
+impl<Ok, Err, Gen: FnMut() -> Result<Ok, Err>, Hook: FnMut(&Waker)> NbFuture<Ok, Err, Gen, Hook> {
+    /// Builds an `NbFuture` whose `hook` is called with the executor's
+    /// [`Waker`] every time the underlying operation reports `WouldBlock`.
+    ///
+    /// This lets a HAL register the waker with an interrupt/EXTI-style event
+    /// source, so the executor only re-polls the future once the peripheral
+    /// has actually become ready, instead of busy-polling it.
+    pub fn with_waker_hook(gen: Gen, hook: Hook) -> Self {
+        Self { gen, hook }
+    }
+}
+
+impl<Ok, Err, Gen: FnMut() -> Result<Ok, Err>, Hook: FnMut(&Waker)> Future
+    for NbFuture<Ok, Err, Gen, Hook>
+{
     type Output = core::result::Result<Ok, Err>;
 
-    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let gen = unsafe { &mut self.get_unchecked_mut().gen };
-        let res = gen();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let res = (this.gen)();
 
         match res {
             Ok(res) => Poll::Ready(Ok(res)),
-            Err(Error::WouldBlock) => Poll::Pending,
+            Err(Error::WouldBlock) => {
+                (this.hook)(cx.waker());
+                Poll::Pending
+            }
             Err(Error::Other(err)) => Poll::Ready(Err(err)),
         }
     }
@@ -339,3 +444,50 @@ macro_rules! fut {
         nb::NbFuture::from(|| $call)
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+
+    fn countdown(remaining: &Cell<u32>) -> Result<(), Infallible> {
+        let left = remaining.get();
+        if left == 0 {
+            Ok(())
+        } else {
+            remaining.set(left - 1);
+            Err(Error::WouldBlock)
+        }
+    }
+
+    #[test]
+    fn returns_ok_once_the_operation_completes_before_the_deadline() {
+        let timer = Cell::new(3);
+        let mut attempts = 0u32;
+
+        let result: core::result::Result<u32, TimeoutError<Infallible>> = block_timeout!(
+            if attempts < 2 {
+                attempts += 1;
+                Err(Error::WouldBlock)
+            } else {
+                Ok(attempts)
+            },
+            countdown(&timer)
+        );
+
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn times_out_when_the_operation_never_completes() {
+        let timer = Cell::new(1);
+
+        let result: core::result::Result<(), TimeoutError<Infallible>> = block_timeout!(
+            Err::<(), Error<Infallible>>(Error::WouldBlock),
+            countdown(&timer)
+        );
+
+        assert_eq!(result, Err(TimeoutError::TimedOut));
+    }
+}